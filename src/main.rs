@@ -6,11 +6,118 @@ use std::{thread, time};
 use std::cmp;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::convert::TryFrom;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use serde::Deserialize;
+use rand::Rng;
 
 // Should be divisible by 3 for [left, center, right]
-const PADDLE_WIDTH: i32 = 12;
-const NUM_ROWS: i32 = 4;
-const BRICKS_PER_ROW: i32 = 6;
+const DEFAULT_PADDLE_WIDTH: i32 = 12;
+const DEFAULT_NUM_ROWS: i32 = 4;
+const DEFAULT_BRICKS_PER_ROW: i32 = 6;
+const DEFAULT_BALL_TICK_MS: u128 = 70;
+const DEFAULT_LOOP_SLEEP_MS: u64 = 10;
+
+const POINTS_PER_BRICK: u32 = 10;
+const CHAIN_BONUS_PER_EXTRA_HIT: u32 = 5;
+const HIGH_SCORE_FILE: &str = ".pongbrickbreaker_scores";
+const MAX_HIGH_SCORES: usize = 10;
+const STARTING_LIVES: i32 = 3;
+const LEVELS_DIR: &str = "levels";
+const CONFIG_FILE_NAME: &str = "pongbrickbreaker.json5";
+
+const POWER_UP_DROP_CHANCE: f64 = 0.2;
+const POWER_UP_DURATION_MS: u128 = 8000;
+const WIDE_PADDLE_WIDTH_BONUS: i32 = 6;
+const SLOW_BALL_TICK_MULTIPLIER: u128 = 2;
+const POWER_UP_TICK_MS: u128 = 70;
+
+const COLOR_PAIR_NORMAL: i16 = 1;
+const COLOR_PAIR_TOUGH: i16 = 2;
+const COLOR_PAIR_TOUGHEST: i16 = 3;
+
+// Picks a glyph for a brick's remaining hp so a chipped brick visibly changes.
+fn disp_char_for_hp(hp: u8) -> u32 {
+    match hp {
+        0 | 1 => '#' as u32,
+        2 => '%' as u32,
+        _ => '@' as u32,
+    }
+}
+
+// Lets level authors request tougher bricks by glyph; anything else is a
+// standard one-hit brick.
+fn hp_for_glyph(glyph: char) -> u8 {
+    match glyph {
+        '%' => 2,
+        '@' => 3,
+        _ => 1,
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+struct Config {
+    paddle_width: i32,
+    num_rows: i32,
+    bricks_per_row: i32,
+    ball_tick_ms: u128,
+    loop_sleep_ms: u64,
+    keys: HashMap<String, char>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut keys = HashMap::new();
+        keys.insert("left".to_string(), 'a');
+        keys.insert("right".to_string(), 'd');
+        keys.insert("quit".to_string(), 'q');
+
+        Config {
+            paddle_width: DEFAULT_PADDLE_WIDTH,
+            num_rows: DEFAULT_NUM_ROWS,
+            bricks_per_row: DEFAULT_BRICKS_PER_ROW,
+            ball_tick_ms: DEFAULT_BALL_TICK_MS,
+            loop_sleep_ms: DEFAULT_LOOP_SLEEP_MS,
+            keys: keys,
+        }
+    }
+}
+
+// Searches the working directory, then a dotfile in $HOME, for a JSON5
+// config. Falls back to defaults if absent, unreadable, or malformed so a
+// bad config can't crash the game before it even starts.
+fn load_config() -> Config {
+    let mut candidates = vec![PathBuf::from(CONFIG_FILE_NAME)];
+    if let Ok(home) = std::env::var("HOME") {
+        candidates.push(PathBuf::from(home).join(format!(".{}", CONFIG_FILE_NAME)));
+    }
+
+    for path in candidates {
+        if let Ok(contents) = fs::read_to_string(&path) {
+            if let Ok(config) = json5::from_str::<Config>(&contents) {
+                return sanitize_config(config);
+            }
+        }
+    }
+
+    Config::default()
+}
+
+// A config file can be valid JSON5 and still be nonsensical (e.g.
+// `bricks_per_row: 0`), which would otherwise divide-by-zero or panic in
+// `build_bricks` before the game even starts. Clamp the numeric fields to
+// sane minimums instead of trusting them verbatim.
+fn sanitize_config(mut config: Config) -> Config {
+    config.paddle_width = cmp::max(config.paddle_width, 1);
+    config.num_rows = cmp::max(config.num_rows, 1);
+    config.bricks_per_row = cmp::max(config.bricks_per_row, 1);
+    config.ball_tick_ms = cmp::max(config.ball_tick_ms, 1);
+    config.loop_sleep_ms = cmp::max(config.loop_sleep_ms, 1);
+    config
+}
 
 enum Direction {
     Left,
@@ -68,6 +175,46 @@ struct Bounds {
     max_y: i32,
 }
 
+impl Bounds {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PowerUpKind {
+    WidePaddle,
+    SlowBall,
+    ExtraBall,
+    MultiHitBall,
+}
+
+impl PowerUpKind {
+    fn disp_char(&self) -> u32 {
+        match self {
+            PowerUpKind::WidePaddle => 'W' as u32,
+            PowerUpKind::SlowBall => 'S' as u32,
+            PowerUpKind::ExtraBall => 'E' as u32,
+            PowerUpKind::MultiHitBall => 'M' as u32,
+        }
+    }
+
+    fn random() -> PowerUpKind {
+        match rand::thread_rng().gen_range(0..4) {
+            0 => PowerUpKind::WidePaddle,
+            1 => PowerUpKind::SlowBall,
+            2 => PowerUpKind::ExtraBall,
+            _ => PowerUpKind::MultiHitBall,
+        }
+    }
+}
+
+// A falling collectible dropped by a destroyed brick.
+struct PowerUp {
+    obj: GameObject,
+    kind: PowerUpKind,
+}
+
 enum MoveResult {
     HitPaddleCenter,
     HitPaddleLeft,
@@ -78,12 +225,15 @@ enum MoveResult {
     HitBrick(Direction, usize),
 }
 
-// Can be a ball, a paddle, or a brick.
+// Can be a ball, a paddle, or a brick. `hp` only matters for bricks: it's
+// the number of hits remaining before `disp_char`/the color pair downgrade
+// and the brick is eventually removed.
 struct GameObject {
     pos: Point,
     vel: Point,
     disp_char: u32,
     width: i32,
+    hp: u8,
 }
 
 impl GameObject {
@@ -136,7 +286,7 @@ impl GameObject {
                 }
 
                 if self.pos.will_collide(paddle_bounds, &Direction::Down) {
-                    let third = PADDLE_WIDTH / 3;
+                    let third = (paddle_bounds.max_x - paddle_bounds.min_x) / 3;
                     if self.pos.x < (paddle_bounds.min_x + third) {
                         return Some(MoveResult::HitPaddleLeft);
                     }
@@ -153,8 +303,8 @@ impl GameObject {
     }
 
     // floats the game object by the velocity
-    fn float(&mut self, screen_bounds: &Bounds, paddle_bounds: &Bounds, brick_bounds: &Vec<Bounds>) -> Result<Option<usize>, String> {
-        let mut hit_brick: Option<usize> = None;
+    fn float(&mut self, screen_bounds: &Bounds, paddle_bounds: &Bounds, brick_bounds: &Vec<Bounds>) -> Result<Vec<usize>, String> {
+        let mut hit_bricks: Vec<usize> = Vec::new();
         let mut lost: bool = false;
         let x_collision: Option<MoveResult> = match self.vel.x {
             x if x < 0 => self.move1(Direction::Left, screen_bounds, paddle_bounds, brick_bounds),
@@ -188,26 +338,30 @@ impl GameObject {
             },
             Some(MoveResult::HitBrick(Direction::Down, brick_idx)) => {
                 self.vel.y = -self.vel.y;
-                hit_brick = Some(brick_idx);
+                hit_bricks.push(brick_idx);
             },
             Some(MoveResult::HitBrick(Direction::Up, brick_idx)) => {
                 self.vel.y = -self.vel.y;
-                hit_brick = Some(brick_idx);
+                hit_bricks.push(brick_idx);
             },
             _ => (),
         };
 
         match x_collision {
             Some(MoveResult::HitBrick(Direction::Left, brick_idx)) => {
-                if !hit_brick.is_some() {
+                if hit_bricks.is_empty() {
                     self.vel.x = -self.vel.x;
-                    hit_brick = Some(brick_idx);
+                }
+                if !hit_bricks.contains(&brick_idx) {
+                    hit_bricks.push(brick_idx);
                 }
             },
             Some(MoveResult::HitBrick(Direction::Right, brick_idx)) => {
-                if !hit_brick.is_some() {
+                if hit_bricks.is_empty() {
                     self.vel.x = -self.vel.x;
-                    hit_brick = Some(brick_idx);
+                }
+                if !hit_bricks.contains(&brick_idx) {
+                    hit_bricks.push(brick_idx);
                 }
             },
             Some(MoveResult::HitWallLeftRight) => self.vel.x = -self.vel.x,
@@ -218,15 +372,29 @@ impl GameObject {
         if lost {
             return Err("Player has lost.".to_string());
         }
-        Ok(hit_brick)
+        Ok(hit_bricks)
+    }
+
+    // the color pair tracks remaining hp so tougher bricks render distinctly;
+    // non-brick objects just get the default (hp 1) pair.
+    fn color_pair(&self) -> i16 {
+        match self.hp {
+            0 | 1 => COLOR_PAIR_NORMAL,
+            2 => COLOR_PAIR_TOUGH,
+            _ => COLOR_PAIR_TOUGHEST,
+        }
     }
 
     fn draw(&self) {
         let start = self.pos.x - self.width / 2;
         let end = self.pos.x + (self.width / 2);
+        attron(COLOR_PAIR(self.color_pair()));
         for x in start..cmp::max(end, start+1) {
             mvaddch(self.pos.y, x, self.disp_char);
         }
+        // restore the background pair rather than attroff, which would
+        // revert to pair 0 instead of the green pair set via wbkgdset
+        attron(COLOR_PAIR(COLOR_PAIR_NORMAL));
     }
 
     fn clear(&self) {
@@ -241,10 +409,24 @@ impl GameObject {
 struct Game {
     bounds: Bounds,
     player: GameObject,
-    ball: GameObject,
+    balls: Vec<GameObject>,
     bricks: Vec<GameObject>,
     window: WINDOW,
     last_ball_move: u128,
+    last_power_up_move: u128,
+    score: u32,
+    lives: i32,
+    served: bool,
+    levels: Vec<PathBuf>,
+    level_index: usize,
+    config: Config,
+    powerups: Vec<PowerUp>,
+    armored_bricks: HashSet<(i32, i32)>,
+    base_paddle_width: i32,
+    base_ball_tick_ms: u128,
+    wide_paddle_expiry: Option<u128>,
+    slow_ball_expiry: Option<u128>,
+    multi_hit_expiry: Option<u128>,
 }
 
 impl Game {
@@ -253,9 +435,49 @@ impl Game {
         self.player.draw();
     }
 
-    fn draw_ball(&mut self) {
-        self.ball.clear();
-        self.ball.draw();
+    fn draw_score(&self) {
+        mvaddstr(0, 2, &format!(" Score: {}  Lives: {} ", self.score, self.lives));
+    }
+
+    // replaces the balls with a single new ball above the paddle and sticks it
+    // there until the player serves with space, rather than ending the
+    // game on a lost ball.
+    fn reset_ball(&mut self) {
+        self.balls = vec![GameObject {
+            pos: Point { x: self.player.pos.x, y: self.player.pos.y - 1 },
+            vel: Point { x: 0, y: 0 },
+            disp_char: '0' as u32,
+            width: 1,
+            hp: 1,
+        }];
+        self.served = false;
+    }
+
+    fn serve_ball(&mut self) {
+        if !self.served {
+            for ball in self.balls.iter_mut() {
+                ball.vel = Point { x: 0, y: -1 };
+            }
+            self.served = true;
+        }
+    }
+
+    // injects an extra ball into play, e.g. from the ExtraBall power-up
+    fn add_ball(&mut self, pos: Point, vel: Point) {
+        self.balls.push(GameObject {
+            pos: pos,
+            vel: vel,
+            disp_char: '0' as u32,
+            width: 1,
+            hp: 1,
+        });
+    }
+
+    fn draw_balls(&mut self) {
+        for ball in self.balls.iter_mut() {
+            ball.clear();
+            ball.draw();
+        }
     }
 
     fn draw_bricks(&mut self) {
@@ -268,19 +490,51 @@ impl Game {
         self.player.clear();
         self.player.move1(direction, &self.bounds, &self.bounds, &vec![]);
         self.draw_player();
+
+        if !self.served {
+            for ball in self.balls.iter_mut() {
+                ball.clear();
+                ball.pos.x = self.player.pos.x;
+                ball.pos.y = self.player.pos.y - 1;
+                ball.draw();
+            }
+        }
     }
 
-    fn move_ball(&mut self) -> Result<Option<usize>, String> {
+    // floats every ball a tick, accumulating brick hits across all of them.
+    // A ball that hits the bottom wall is dropped from the vector; the
+    // caller checks `balls.is_empty()` to decide whether a life was lost.
+    fn move_balls(&mut self) -> Vec<usize> {
+        if !self.served {
+            return Vec::new();
+        }
+
         let now = now_ms();
-        if now - self.last_ball_move > 70 {
-            let brick_bounds = self.get_brick_bounds();
-            self.last_ball_move = now;
-            self.ball.clear();
-            let result = self.ball.float(&self.bounds, &self.player.get_bounds(), &brick_bounds);
-            self.draw_ball();
-            return result;
+        if now - self.last_ball_move <= self.config.ball_tick_ms {
+            return Vec::new();
         }
-        Ok(None)
+        self.last_ball_move = now;
+
+        let brick_bounds = self.get_brick_bounds();
+        let paddle_bounds = self.player.get_bounds();
+        let mut hit_bricks = Vec::new();
+
+        let mut i = 0;
+        while i < self.balls.len() {
+            self.balls[i].clear();
+            match self.balls[i].float(&self.bounds, &paddle_bounds, &brick_bounds) {
+                Ok(mut hits) => {
+                    hit_bricks.append(&mut hits);
+                    self.balls[i].draw();
+                    i += 1;
+                },
+                Err(_) => {
+                    self.balls.remove(i);
+                },
+            }
+        }
+
+        hit_bricks
     }
 
     fn get_brick_bounds(&self) -> Vec<Bounds> {
@@ -292,32 +546,302 @@ impl Game {
         brick_bounds
     }
 
+    // clears the current bricks and repopulates from the parsed level file,
+    // returning false (leaving self.bricks untouched) if the file is missing
+    // or doesn't parse into any bricks.
+    fn load_level(&mut self, path: &PathBuf) -> bool {
+        match parse_level_file(path, self.bounds.max_x) {
+            Some(bricks) => {
+                self.bricks = bricks;
+                true
+            },
+            None => false,
+        }
+    }
+
     fn rm_brick(&mut self, brick_idx: usize) {
         assert!(brick_idx <= self.bricks.len());
         self.bricks[brick_idx].clear();
         self.bricks.remove(brick_idx);
+        self.score += POINTS_PER_BRICK;
+    }
+
+    // Applies one hit to the brick at `idx`, possibly spawning a power-up.
+    // Returns true if the brick was destroyed. While a MultiHitBall effect
+    // is active, a brick's first hit just chips it (tracked by position,
+    // since indices shift as bricks are removed from one tick to the next).
+    fn hit_brick(&mut self, idx: usize) -> bool {
+        let key = (self.bricks[idx].pos.x, self.bricks[idx].pos.y);
+        let multi_hit_active = self.multi_hit_expiry.map_or(false, |expiry| now_ms() < expiry);
+
+        if multi_hit_active && self.armored_bricks.insert(key) {
+            self.bricks[idx].hp += 1;
+        }
+
+        if self.bricks[idx].hp > 1 {
+            self.bricks[idx].hp -= 1;
+            self.bricks[idx].disp_char = disp_char_for_hp(self.bricks[idx].hp);
+            self.bricks[idx].clear();
+            self.bricks[idx].draw();
+            self.score += POINTS_PER_BRICK;
+            return false;
+        }
+
+        self.armored_bricks.remove(&key);
+        self.rm_brick(idx);
+        self.maybe_spawn_power_up(Point { x: key.0, y: key.1 });
+        true
+    }
+
+    // applies one hit to each brick hit during a single `move_balls` tick
+    // (across all balls, each brick at most once), awarding a chain bonus
+    // when more than one brick was destroyed in that tick.
+    fn rm_bricks(&mut self, mut brick_idxs: Vec<usize>) {
+        brick_idxs.sort_unstable_by(|a, b| b.cmp(a));
+        brick_idxs.dedup();
+
+        let destroyed = brick_idxs.iter().filter(|idx| self.hit_brick(**idx)).count();
+        if destroyed > 1 {
+            self.score += CHAIN_BONUS_PER_EXTRA_HIT * (destroyed as u32 - 1);
+        }
+    }
+
+    fn maybe_spawn_power_up(&mut self, pos: Point) {
+        if rand::thread_rng().gen::<f64>() >= POWER_UP_DROP_CHANCE {
+            return;
+        }
+
+        let kind = PowerUpKind::random();
+        self.powerups.push(PowerUp {
+            obj: GameObject {
+                pos: pos,
+                vel: Point { x: 0, y: 1 },
+                disp_char: kind.disp_char(),
+                width: 1,
+                hp: 1,
+            },
+            kind: kind,
+        });
+    }
+
+    // Ticks every falling power-up downward on the same kind of timed gate
+    // `move_balls` uses (rather than every loop iteration, which would drop
+    // them far faster than a ball falls), drops the ones that reach the
+    // floor, and applies the effect of any that reach the paddle.
+    fn update_power_ups(&mut self) {
+        let now = now_ms();
+        if now - self.last_power_up_move <= POWER_UP_TICK_MS {
+            return;
+        }
+        self.last_power_up_move = now;
+
+        let paddle_bounds = self.player.get_bounds();
+        let max_y = self.bounds.max_y;
+        let brick_bounds = self.get_brick_bounds();
+        let mut collected = Vec::new();
+
+        let mut i = 0;
+        while i < self.powerups.len() {
+            let old_x = self.powerups[i].obj.pos.x;
+            let old_y = self.powerups[i].obj.pos.y;
+            // a brick occupies this cell; don't blank it out from under
+            // itself, the power-up is just passing over it
+            let over_brick = brick_bounds.iter().any(|b| b.contains(old_x, old_y));
+            if !over_brick {
+                self.powerups[i].obj.clear();
+            }
+
+            self.powerups[i].obj.pos.y += 1;
+
+            let caught = paddle_bounds.contains(self.powerups[i].obj.pos.x, self.powerups[i].obj.pos.y);
+            if self.powerups[i].obj.pos.y >= max_y || caught {
+                if caught {
+                    collected.push(self.powerups[i].kind);
+                }
+                if over_brick {
+                    self.redraw_brick_at(old_x, old_y);
+                }
+                self.powerups.remove(i);
+                continue;
+            }
+
+            self.powerups[i].obj.draw();
+            if over_brick {
+                self.redraw_brick_at(old_x, old_y);
+            }
+            i += 1;
+        }
+
+        for kind in collected {
+            self.apply_power_up(kind, now);
+        }
+        self.expire_power_ups(now);
+    }
+
+    // redraws whichever brick occupies (x, y), if any; used to repair a
+    // brick cell a falling power-up just vacated
+    fn redraw_brick_at(&mut self, x: i32, y: i32) {
+        if let Some(brick) = self.bricks.iter().find(|brick| brick.get_bounds().contains(x, y)) {
+            brick.draw();
+        }
+    }
+
+    fn apply_power_up(&mut self, kind: PowerUpKind, now: u128) {
+        let expiry = now + POWER_UP_DURATION_MS;
+        match kind {
+            PowerUpKind::WidePaddle => {
+                self.player.clear();
+                self.player.width = self.base_paddle_width + WIDE_PADDLE_WIDTH_BONUS;
+                self.player.draw();
+                self.wide_paddle_expiry = Some(expiry);
+            },
+            PowerUpKind::SlowBall => {
+                self.config.ball_tick_ms = self.base_ball_tick_ms * SLOW_BALL_TICK_MULTIPLIER;
+                self.slow_ball_expiry = Some(expiry);
+            },
+            PowerUpKind::MultiHitBall => {
+                self.multi_hit_expiry = Some(expiry);
+            },
+            PowerUpKind::ExtraBall => {
+                let pos = Point { x: self.player.pos.x, y: self.player.pos.y - 1 };
+                self.add_ball(pos, Point { x: 0, y: -1 });
+            },
+        }
+    }
+
+    fn expire_power_ups(&mut self, now: u128) {
+        if self.wide_paddle_expiry.map_or(false, |expiry| now >= expiry) {
+            self.player.clear();
+            self.player.width = self.base_paddle_width;
+            self.player.draw();
+            self.wide_paddle_expiry = None;
+        }
+        if self.slow_ball_expiry.map_or(false, |expiry| now >= expiry) {
+            self.config.ball_tick_ms = self.base_ball_tick_ms;
+            self.slow_ball_expiry = None;
+        }
+        if self.multi_hit_expiry.map_or(false, |expiry| now >= expiry) {
+            self.multi_hit_expiry = None;
+            self.armored_bricks.clear();
+        }
+    }
+}
+
+#[derive(Clone)]
+struct HighScoreEntry {
+    name: String,
+    score: u32,
+}
+
+fn high_score_path() -> PathBuf {
+    match std::env::var("HOME") {
+        Ok(home) => PathBuf::from(home).join(HIGH_SCORE_FILE),
+        Err(_) => PathBuf::from(HIGH_SCORE_FILE),
+    }
+}
+
+// Parses `name\tscore` pairs, one per line. A missing or corrupt file just
+// yields an empty table rather than failing the game.
+fn load_high_scores(path: &PathBuf) -> Vec<HighScoreEntry> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut scores: Vec<HighScoreEntry> = contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '\t');
+            let name = parts.next()?.to_string();
+            let score: u32 = parts.next()?.trim().parse().ok()?;
+            Some(HighScoreEntry { name, score })
+        })
+        .collect();
+
+    scores.sort_by(|a, b| b.score.cmp(&a.score));
+    scores
+}
+
+fn save_high_scores(path: &PathBuf, scores: &[HighScoreEntry]) {
+    let mut contents = String::new();
+    for entry in scores {
+        contents.push_str(&format!("{}\t{}\n", entry.name, entry.score));
+    }
+
+    if let Ok(mut file) = fs::File::create(path) {
+        let _ = file.write_all(contents.as_bytes());
     }
 }
 
+// Prompts for a player name on the message line near the bottom of the
+// board, temporarily re-enabling echo so getstr shows what's typed.
+fn prompt_name(window: WINDOW, max_y: i32) -> String {
+    echo();
+    curs_set(CURSOR_VISIBILITY::CURSOR_VISIBLE);
+    nodelay(window, false);
+
+    mvaddstr(max_y - 2, 2, "New high score! Enter your name: ");
+    refresh();
+
+    let mut buf = String::new();
+    getstr(&mut buf);
+
+    nodelay(window, true);
+    curs_set(CURSOR_INVISIBLE);
+    noecho();
+
+    let name = buf.trim();
+    if name.is_empty() {
+        "Anonymous".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn draw_high_scores(window: WINDOW, max_y: i32, scores: &[HighScoreEntry]) {
+    clear();
+    attron(A_BOLD());
+    box_(window, 0, 0);
+    attroff(A_BOLD());
+
+    mvaddstr(1, 2, "High Scores");
+    for (idx, entry) in scores.iter().enumerate() {
+        mvaddstr(3 + idx as i32, 2, &format!("{:>2}. {:<20} {}", idx + 1, entry.name, entry.score));
+    }
+    mvaddstr(cmp::min(max_y - 2, 4 + scores.len() as i32), 2, "Press any key to continue...");
+    refresh();
+
+    nodelay(window, false);
+    wgetch(window);
+}
+
 enum Command {
     Move(Direction),
+    Serve,
     Quit,
 }
 
 impl Command {
-    fn from_char(c: char) -> Command {
-        match c {
-            'a' => return Command::Move(Direction::Left),
-            'd' => return Command::Move(Direction::Right),
-            'q' => return Command::Quit,
-            _ => return Command::Move(Direction::Still),
-        };
+    fn from_char(c: char, config: &Config) -> Command {
+        if c == ' ' {
+            return Command::Serve;
+        }
+        if Some(&c) == config.keys.get("left") {
+            return Command::Move(Direction::Left);
+        }
+        if Some(&c) == config.keys.get("right") {
+            return Command::Move(Direction::Right);
+        }
+        if Some(&c) == config.keys.get("quit") {
+            return Command::Quit;
+        }
+        Command::Move(Direction::Still)
     }
 
-    fn from_i32(i: i32) -> Command {
+    fn from_i32(i: i32, config: &Config) -> Command {
         match char::from_u32(i as u32) {
-            Some(ch) => return Command::from_char(ch),
-            None => return Command::Move(Direction::Still), 
+            Some(ch) => return Command::from_char(ch, config),
+            None => return Command::Move(Direction::Still),
         };
     }
 }
@@ -341,8 +865,10 @@ fn init() -> Result<WINDOW, String> {
 
     start_color();
 
-    init_pair(1, COLOR_GREEN, COLOR_BLACK);
-    wbkgdset(window, COLOR_PAIR(1));
+    init_pair(COLOR_PAIR_NORMAL, COLOR_GREEN, COLOR_BLACK);
+    init_pair(COLOR_PAIR_TOUGH, COLOR_YELLOW, COLOR_BLACK);
+    init_pair(COLOR_PAIR_TOUGHEST, COLOR_RED, COLOR_BLACK);
+    wbkgdset(window, COLOR_PAIR(COLOR_PAIR_NORMAL));
 
     attron(A_BOLD());
     box_(window, 0, 0);
@@ -352,41 +878,73 @@ fn init() -> Result<WINDOW, String> {
 }
 
 fn run(game: &mut Game) -> String {
-    let ten_millis = time::Duration::from_millis(10);
- 
+    let loop_sleep = time::Duration::from_millis(game.config.loop_sleep_ms);
+    nodelay(game.window, true);
+
     game.draw_bricks();
     game.draw_player();
-    game.draw_ball();
+    game.draw_balls();
+    game.draw_score();
     refresh();
 
     loop {
-        thread::sleep(ten_millis);
-        match Command::from_i32(wgetch(game.window)) {
+        thread::sleep(loop_sleep);
+        match Command::from_i32(wgetch(game.window), &game.config) {
             Command::Move(direction) => {
                 game.move_player(direction);
             },
+            Command::Serve => {
+                game.serve_ball();
+            },
             Command::Quit => return "Bye!".to_string(),
         };
-        let result = game.move_ball();
-        match result {
-            Ok(hit_brick) => {
-                match hit_brick {
-                    Some(brick_idx) => game.rm_brick(brick_idx),
-                    None => (),
-                }
-            },
-            Err(_) => {
+
+        let hit_bricks = game.move_balls();
+        if !hit_bricks.is_empty() {
+            game.rm_bricks(hit_bricks);
+            game.draw_score();
+        }
+
+        if game.balls.is_empty() {
+            game.lives -= 1;
+            if game.lives <= 0 {
                 return "You lost :(".to_string();
             }
+            game.reset_ball();
+            game.draw_score();
         }
 
+        game.update_power_ups();
+
         if game.bricks.len() == 0 {
+            let next_level = game.levels.get(game.level_index + 1).cloned();
+            if let Some(path) = next_level {
+                game.level_index += 1;
+                if game.load_level(&path) {
+                    game.draw_bricks();
+                    game.draw_score();
+                    refresh();
+                    continue;
+                }
+            }
             return "You won! :)".to_string();
         }
         refresh();
     }
 }
 
+// blocks until the player hits space (play again) or q (quit)
+fn prompt_play_again(window: WINDOW, config: &Config) -> bool {
+    nodelay(window, false);
+    loop {
+        match Command::from_i32(wgetch(window), config) {
+            Command::Serve => return true,
+            Command::Quit => return false,
+            _ => (),
+        }
+    }
+}
+
 fn now_ms() -> u128 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -394,40 +952,93 @@ fn now_ms() -> u128 {
         .as_millis()
 }
 
-fn main() {
-    let window = match init() {
-        Ok(window) => window,
-        Err(error) => {
-            println!("Error creating window: {}\n", error);
-            process::exit(1);
-        },
+// Finds level files in the `levels/` directory, sorted by name so e.g.
+// `01.txt`, `02.txt` load in order. Returns an empty vec if the directory
+// doesn't exist, so the caller can fall back to the procedural grid.
+fn discover_levels() -> Vec<PathBuf> {
+    let dir = Path::new(LEVELS_DIR);
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
     };
 
-    let mut max_x: i32 = 0;
-    let mut max_y: i32 = 0;
-    getmaxyx(window, &mut max_y, &mut max_x);
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
 
-    let brick_width = max_x / BRICKS_PER_ROW;
-    let capacity = usize::try_from((BRICKS_PER_ROW - 1) * NUM_ROWS).unwrap();
+// Parses an ASCII board map: one glyph per brick cell, spaces for gaps.
+// Each non-space character becomes a brick positioned on a grid derived
+// from the screen width and the line's column.
+fn parse_level_file(path: &PathBuf, max_x: i32) -> Option<Vec<GameObject>> {
+    let contents = fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let cols = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    if cols == 0 {
+        return None;
+    }
+
+    let brick_width = max_x / cols as i32;
+    let mut bricks = Vec::new();
+    for (row, line) in lines.iter().enumerate() {
+        for (col, glyph) in line.chars().enumerate() {
+            if glyph == ' ' {
+                continue;
+            }
+            bricks.push(GameObject {
+                pos: Point { x: (col as i32 * brick_width) + (brick_width / 2), y: row as i32 + 1 },
+                vel: Point { x: 0, y: 0 },
+                disp_char: glyph as u32,
+                width: brick_width,
+                hp: hp_for_glyph(glyph),
+            });
+        }
+    }
+
+    if bricks.is_empty() {
+        None
+    } else {
+        Some(bricks)
+    }
+}
+
+fn build_bricks(max_x: i32, config: &Config) -> Vec<GameObject> {
+    let brick_width = max_x / config.bricks_per_row;
+    let capacity = usize::try_from((config.bricks_per_row - 1) * config.num_rows).unwrap();
     let mut bricks = Vec::with_capacity(capacity);
-    for row in 1..NUM_ROWS+1 {
+    for row in 1..config.num_rows+1 {
         let offset = match row % 2 {
             0 => (brick_width / 2),
             _ => (brick_width / 2) - (brick_width / 4),
         };
-        for col in 0..(BRICKS_PER_ROW - 1) {
+        // earlier rows are tougher, capping at a 3-hit brick
+        let hp = cmp::max(1, cmp::min(config.num_rows - row + 1, 3)) as u8;
+        for col in 0..(config.bricks_per_row - 1) {
             bricks.push(
                 GameObject {
                     pos: Point { x: offset + (col * brick_width) + (brick_width / 2), y: row },
                     vel: Point { x: 0, y: 0 },
-                    disp_char: '#' as u32,
+                    disp_char: disp_char_for_hp(hp),
                     width: brick_width,
+                    hp: hp,
                 }
             );
         }
     }
+    bricks
+}
 
-    let mut game = Game {
+fn new_game(window: WINDOW, max_x: i32, max_y: i32, config: Config) -> Game {
+    let levels = discover_levels();
+    let bricks = levels.get(0)
+        .and_then(|path| parse_level_file(path, max_x))
+        .unwrap_or_else(|| build_bricks(max_x, &config));
+
+    Game {
         window: window,
         bounds: Bounds { max_x: max_x, max_y: max_y, min_x: 0, min_y: 0 },
         // we want the paddle to be above the bottom border of the screen
@@ -435,19 +1046,86 @@ fn main() {
             pos: Point { x: (max_x / 2), y: max_y - 4},
             vel: Point { x: 0, y: 0 },
             disp_char: '=' as u32,
-            width: PADDLE_WIDTH,
+            width: config.paddle_width,
+            hp: 1,
         },
-        ball: GameObject {
-            pos: Point { x: (max_x / 2), y: 7 },
-            vel: Point { x: 0, y: 1 },
+        balls: vec![GameObject {
+            pos: Point { x: (max_x / 2), y: max_y - 5 },
+            vel: Point { x: 0, y: 0 },
             disp_char: '0' as u32,
             width: 1,
-        },
+            hp: 1,
+        }],
         bricks: bricks,
-        last_ball_move: now_ms()
+        last_ball_move: now_ms(),
+        last_power_up_move: now_ms(),
+        score: 0,
+        lives: STARTING_LIVES,
+        served: false,
+        levels: levels,
+        level_index: 0,
+        powerups: Vec::new(),
+        armored_bricks: HashSet::new(),
+        base_paddle_width: config.paddle_width,
+        base_ball_tick_ms: config.ball_tick_ms,
+        wide_paddle_expiry: None,
+        slow_ball_expiry: None,
+        multi_hit_expiry: None,
+        config: config,
+    }
+}
+
+fn main() {
+    let window = match init() {
+        Ok(window) => window,
+        Err(error) => {
+            println!("Error creating window: {}\n", error);
+            process::exit(1);
+        },
     };
-    
-    let msg = run(&mut game);
+
+    let mut max_x: i32 = 0;
+    let mut max_y: i32 = 0;
+    getmaxyx(window, &mut max_y, &mut max_x);
+
+    let config = load_config();
+    let mut game = new_game(window, max_x, max_y, config.clone());
+    let mut msg;
+
+    loop {
+        msg = run(&mut game);
+
+        if msg == "Bye!" {
+            break;
+        }
+
+        let path = high_score_path();
+        let mut scores = load_high_scores(&path);
+        let made_list = scores.len() < MAX_HIGH_SCORES || scores.iter().any(|entry| game.score > entry.score);
+
+        if made_list && game.score > 0 {
+            let name = prompt_name(window, max_y);
+            scores.push(HighScoreEntry { name, score: game.score });
+            scores.sort_by(|a, b| b.score.cmp(&a.score));
+            scores.truncate(MAX_HIGH_SCORES);
+            save_high_scores(&path, &scores);
+        }
+
+        draw_high_scores(window, max_y, &scores);
+
+        clear();
+        attron(A_BOLD());
+        box_(window, 0, 0);
+        attroff(A_BOLD());
+        mvaddstr(max_y / 2, 2, "Hit space to play again or q to quit");
+        refresh();
+
+        if prompt_play_again(window, &config) {
+            game = new_game(window, max_x, max_y, config.clone());
+        } else {
+            break;
+        }
+    }
 
     endwin();
     println!("{}", msg);